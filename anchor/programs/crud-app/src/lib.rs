@@ -1,6 +1,8 @@
 #![allow(clippy::result_large_err)]
 
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer as TokenTransfer};
 
 declare_id!("5Bww75bUi5z4efKDNH9EJQf7Vk1HFjwkCe4261ifrY2x");
 
@@ -9,25 +11,201 @@ pub mod crud_app {
     use super::*;
 
     pub fn create_journal_entry(ctx: Context<CreateJournalEntry>, title: String, message: String) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
         let journal_entry = &mut ctx.accounts.journal_entry;
         journal_entry.owner = ctx.accounts.owner.key();
         journal_entry.title = title;
         journal_entry.message = message;
+        journal_entry.created_at = now;
+        journal_entry.updated_at = now;
+
+        emit!(JournalNoteCreated {
+            actor: journal_entry.owner,
+            name: journal_entry.title.clone(),
+            content: journal_entry.message.clone(),
+            published: now,
+        });
+
         Ok(())
     }
 
+    #[access_control(authorize_editor(&ctx))]
     pub fn update_journal_entry(ctx: Context<UpdateJournalEntry>, _title: String, message: String) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
         let journal_entry = &mut ctx.accounts.journal_entry;
-        
         journal_entry.message = message;
+        journal_entry.updated_at = now;
+
+        emit!(JournalNoteUpdated {
+            actor: journal_entry.owner,
+            name: journal_entry.title.clone(),
+            content: journal_entry.message.clone(),
+            published: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn add_editor(ctx: Context<AddEditor>, _title: String, editor: Pubkey) -> Result<()> {
+        let journal_entry = &mut ctx.accounts.journal_entry;
+        require!(
+            !journal_entry.editors.contains(&editor),
+            JournalError::EditorAlreadyPresent
+        );
+        require!(
+            journal_entry.editors.len() < 5,
+            JournalError::TooManyEditors
+        );
+        journal_entry.editors.push(editor);
+
+        Ok(())
+    }
+
+    pub fn remove_editor(ctx: Context<RemoveEditor>, _title: String, editor: Pubkey) -> Result<()> {
+        let journal_entry = &mut ctx.accounts.journal_entry;
+        let len_before = journal_entry.editors.len();
+        journal_entry.editors.retain(|e| e != &editor);
+        require!(
+            journal_entry.editors.len() < len_before,
+            JournalError::EditorNotFound
+        );
+
+        Ok(())
+    }
+
+    pub fn delete_journal_entry(ctx: Context<DeleteJournalEntry>, _title: String) -> Result<()> {
+        require!(ctx.accounts.vault.lamports() == 0, JournalError::VaultNotEmpty);
+
+        emit!(JournalNoteDeleted {
+            actor: ctx.accounts.journal_entry.owner,
+            name: ctx.accounts.journal_entry.title.clone(),
+            published: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn tip_journal_entry(ctx: Context<TipJournalEntry>, _title: String, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.tipper.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?;
+
+        let vault = &mut ctx.accounts.vault;
+        vault.bump = ctx.bumps.vault;
+
+        let journal_entry = &mut ctx.accounts.journal_entry;
+        journal_entry.total_tips = journal_entry
+            .total_tips
+            .checked_add(amount)
+            .ok_or(JournalError::Overflow)?;
+
+        Ok(())
+    }
+
+    pub fn withdraw_tips(ctx: Context<WithdrawTips>, _title: String) -> Result<()> {
+        let vault = ctx.accounts.vault.to_account_info();
+        let owner = ctx.accounts.owner.to_account_info();
+
+        let balance = vault.lamports(); // drain fully, don't leave the rent-exempt reserve behind
+        require!(balance > 0, JournalError::VaultEmpty);
+
+        **vault.try_borrow_mut_lamports()? -= balance;
+        **owner.try_borrow_mut_lamports()? += balance;
+
+        Ok(())
+    }
+
+    pub fn create_long_entry(ctx: Context<CreateLongEntry>, title: String) -> Result<()> {
+        let title_bytes = title.as_bytes();
+        require!(
+            title_bytes.len() <= MAX_LONG_TITLE_LEN,
+            JournalError::TitleTooLong
+        );
+
+        let mut journal_entry = ctx.accounts.journal_entry.load_init()?;
+        journal_entry.owner = ctx.accounts.owner.key();
+        journal_entry.title[..title_bytes.len()].copy_from_slice(title_bytes);
+        journal_entry.title_len = title_bytes.len() as u8;
+        journal_entry.content_len = 0;
+
+        Ok(())
+    }
+
+    pub fn append_long_entry(ctx: Context<AppendLongEntry>, _title: String, chunk: Vec<u8>) -> Result<()> {
+        let mut journal_entry = ctx.accounts.journal_entry.load_mut()?;
+
+        let start = journal_entry.content_len as usize;
+        let end = start
+            .checked_add(chunk.len())
+            .ok_or(JournalError::ContentTooLong)?;
+        require!(end <= journal_entry.content.len(), JournalError::ContentTooLong);
+
+        journal_entry.content[start..end].copy_from_slice(&chunk);
+        journal_entry.content_len = end as u32;
+
         Ok(())
     }
 
-    pub fn delete_journal_entry(_ctx: Context<DeleteJournalEntry>, _title: String) -> Result<()> {
-        
+    pub fn delete_long_entry(_ctx: Context<DeleteLongEntry>, _title: String) -> Result<()> {
         Ok(())
     }
 
+    pub fn create_paid_entry(
+        ctx: Context<CreatePaidEntry>,
+        title: String,
+        message: String,
+        price: u64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        let journal_entry = &mut ctx.accounts.journal_entry;
+        journal_entry.owner = ctx.accounts.owner.key();
+        journal_entry.title = title;
+        journal_entry.message = message;
+        journal_entry.created_at = now;
+        journal_entry.updated_at = now;
+        journal_entry.price = price;
+        journal_entry.mint = ctx.accounts.mint.key();
+
+        emit!(JournalNoteCreated {
+            actor: journal_entry.owner,
+            name: journal_entry.title.clone(),
+            content: journal_entry.message.clone(),
+            published: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn unlock_entry(ctx: Context<UnlockEntry>, _title: String) -> Result<()> {
+        let cpi_accounts = TokenTransfer {
+            from: ctx.accounts.reader_token_account.to_account_info(),
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            authority: ctx.accounts.reader.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, ctx.accounts.journal_entry.price)?;
+
+        ctx.accounts.access_receipt.bump = ctx.bumps.access_receipt;
+
+        Ok(())
+    }
+
+}
+
+fn authorize_editor(ctx: &Context<UpdateJournalEntry>) -> Result<()> {
+    let journal_entry = &ctx.accounts.journal_entry;
+    let signer = ctx.accounts.signer.key();
+    require!(
+        signer == journal_entry.owner || journal_entry.editors.contains(&signer),
+        JournalError::NotAuthorized
+    );
+    Ok(())
 }
 
 #[account]
@@ -38,6 +216,41 @@ pub struct JournalEntryState {
     pub title: String,
     #[max_len(1000)]
     pub message: String,
+    pub total_tips: u64,
+    pub created_at: i64,
+    pub updated_at: i64,
+    #[max_len(5)]
+    pub editors: Vec<Pubkey>,
+    pub price: u64,
+    pub mint: Pubkey,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub bump: u8,
+}
+
+// The receipt's existence is the on-chain entitlement: an off-chain client
+// checks for this PDA before decrypting/displaying a paid entry's message.
+#[account]
+#[derive(InitSpace)]
+pub struct AccessReceipt {
+    pub bump: u8,
+}
+
+pub const MAX_LONG_TITLE_LEN: usize = 50;
+pub const MAX_LONG_CONTENT_LEN: usize = 8192;
+
+// zero-copy counterpart to JournalEntryState with an inline buffer instead of realloc
+#[account(zero_copy)]
+#[derive(InitSpace)]
+pub struct LongJournalEntryState {
+    pub owner: Pubkey,
+    pub title: [u8; MAX_LONG_TITLE_LEN],
+    pub title_len: u8,
+    pub content: [u8; MAX_LONG_CONTENT_LEN],
+    pub content_len: u32,
 }
 
 #[derive(Accounts)]
@@ -62,18 +275,21 @@ pub struct CreateJournalEntry<'info> {
 #[instruction(_title: String)]
 pub struct UpdateJournalEntry<'info> {
     #[account(
-        mut, 
-        seeds = [_title.as_bytes(), owner.key().as_ref()], 
+        mut,
+        seeds = [_title.as_bytes(), owner.key().as_ref()],
         bump,
         realloc = 8 + JournalEntryState::INIT_SPACE,
-        realloc::payer = owner,
+        realloc::payer = signer,
         realloc::zero = true // clear the old data
     )]
     pub journal_entry: Account<'info, JournalEntryState>,
-    
+
+    /// CHECK: supplies the key the journal_entry seeds are derived from; who may actually write is decided by `authorize_editor` against `signer`, not this account.
+    pub owner: UncheckedAccount<'info>,
+
     #[account(mut)]
-    pub owner: Signer<'info>,
-    
+    pub signer: Signer<'info>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -87,9 +303,252 @@ pub struct DeleteJournalEntry<'info> {
         close = owner // it has to be the same as the owner
     )]
     pub journal_entry: Account<'info, JournalEntryState>,
-    
+
+    /// CHECK: only read for its lamport balance; the vault's data, if any, is untouched here.
+    #[account(
+        seeds = [b"vault", _title.as_bytes(), owner.key().as_ref()],
+        bump,
+    )]
+    pub vault: UncheckedAccount<'info>,
+
     #[account(mut)]
     pub owner: Signer<'info>,
-    
+
     pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_title: String)]
+pub struct AddEditor<'info> {
+    #[account(
+        mut,
+        seeds = [_title.as_bytes(), owner.key().as_ref()],
+        bump,
+        has_one = owner,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_title: String)]
+pub struct RemoveEditor<'info> {
+    #[account(
+        mut,
+        seeds = [_title.as_bytes(), owner.key().as_ref()],
+        bump,
+        has_one = owner,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_title: String)]
+pub struct TipJournalEntry<'info> {
+    #[account(
+        mut,
+        seeds = [_title.as_bytes(), owner.key().as_ref()],
+        bump,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    #[account(
+        init_if_needed,
+        seeds = [b"vault", _title.as_bytes(), owner.key().as_ref()],
+        bump,
+        payer = tipper,
+        space = 8 + Vault::INIT_SPACE,
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// CHECK: supplies the key the journal_entry and vault seeds are derived from; a wrong value just makes those seeds constraints fail, so no further checks are needed here.
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub tipper: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_title: String)]
+pub struct WithdrawTips<'info> {
+    #[account(
+        seeds = [_title.as_bytes(), owner.key().as_ref()],
+        bump,
+        has_one = owner,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", _title.as_bytes(), owner.key().as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(title: String)]
+pub struct CreateLongEntry<'info> {
+    #[account(
+        init,
+        seeds = [b"long", title.as_bytes(), owner.key().as_ref()],
+        bump,
+        space = 8 + LongJournalEntryState::INIT_SPACE,
+        payer = owner,
+    )]
+    pub journal_entry: AccountLoader<'info, LongJournalEntryState>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_title: String)]
+pub struct AppendLongEntry<'info> {
+    #[account(
+        mut,
+        seeds = [b"long", _title.as_bytes(), owner.key().as_ref()],
+        bump,
+    )]
+    pub journal_entry: AccountLoader<'info, LongJournalEntryState>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(_title: String)]
+pub struct DeleteLongEntry<'info> {
+    #[account(
+        mut,
+        seeds = [b"long", _title.as_bytes(), owner.key().as_ref()],
+        bump,
+        close = owner,
+    )]
+    pub journal_entry: AccountLoader<'info, LongJournalEntryState>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(title: String)]
+pub struct CreatePaidEntry<'info> {
+    #[account(
+        init,
+        seeds = [title.as_bytes(), owner.key().as_ref()],
+        bump,
+        space = 8 + JournalEntryState::INIT_SPACE,
+        payer = owner
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(_title: String)]
+pub struct UnlockEntry<'info> {
+    #[account(
+        seeds = [_title.as_bytes(), owner.key().as_ref()],
+        bump,
+    )]
+    pub journal_entry: Account<'info, JournalEntryState>,
+
+    #[account(
+        init,
+        seeds = [b"access", _title.as_bytes(), owner.key().as_ref(), reader.key().as_ref()],
+        bump,
+        payer = reader,
+        space = 8 + AccessReceipt::INIT_SPACE,
+    )]
+    pub access_receipt: Account<'info, AccessReceipt>,
+
+    #[account(
+        mut,
+        constraint = reader_token_account.mint == journal_entry.mint @ JournalError::WrongMint,
+        constraint = reader_token_account.owner == reader.key() @ JournalError::WrongTokenAccountOwner,
+    )]
+    pub reader_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == journal_entry.mint @ JournalError::WrongMint,
+        constraint = owner_token_account.owner == journal_entry.owner @ JournalError::WrongTokenAccountOwner,
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: supplies the key the journal_entry/access_receipt seeds are derived from; the owner of record is `journal_entry.owner`, which the token account constraints above are checked against.
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub reader: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+// maps to an ActivityStreams Note: actor -> attributedTo, name -> name, content -> content
+#[event]
+pub struct JournalNoteCreated {
+    pub actor: Pubkey,
+    pub name: String,
+    pub content: String,
+    pub published: i64,
+}
+
+#[event]
+pub struct JournalNoteUpdated {
+    pub actor: Pubkey,
+    pub name: String,
+    pub content: String,
+    pub published: i64,
+}
+
+#[event]
+pub struct JournalNoteDeleted {
+    pub actor: Pubkey,
+    pub name: String,
+    pub published: i64,
+}
+
+#[error_code]
+pub enum JournalError {
+    #[msg("Vault still holds lamports; withdraw tips before deleting this entry.")]
+    VaultNotEmpty,
+    #[msg("Vault has no tips to withdraw.")]
+    VaultEmpty,
+    #[msg("Total tips overflowed u64.")]
+    Overflow,
+    #[msg("Title exceeds the maximum length for a long-form entry.")]
+    TitleTooLong,
+    #[msg("Appending this chunk would exceed the long-form entry's content buffer.")]
+    ContentTooLong,
+    #[msg("Signer is neither the owner nor a registered editor of this entry.")]
+    NotAuthorized,
+    #[msg("This pubkey is already an editor of this entry.")]
+    EditorAlreadyPresent,
+    #[msg("This entry already has the maximum number of editors.")]
+    TooManyEditors,
+    #[msg("This pubkey is not an editor of this entry.")]
+    EditorNotFound,
+    #[msg("Token account mint does not match this entry's mint.")]
+    WrongMint,
+    #[msg("Token account is not owned by the expected party.")]
+    WrongTokenAccountOwner,
 }
\ No newline at end of file